@@ -0,0 +1,95 @@
+//! Export of [decorations](super::Decoration) to the Language Server Protocol.
+//!
+//! Parsing produces a `SpanVec<Decoration>` of semantic-highlight spans. This
+//! module encodes them into the LSP `textDocument/semanticTokens` wire format:
+//! a flat `Vec<u32>` of five-element groups
+//! `[deltaLine, deltaStartChar, length, tokenType, tokenModifiers]`, relative to
+//! the previous token. Positions and lengths are counted in UTF-16 code units,
+//! as the protocol requires.
+
+use super::Decoration;
+use super::span::SpanVec;
+
+/// The token types advertised in the legend, in index order.
+///
+/// A server advertises these in its `semanticTokensProvider` capability; the
+/// `tokenType` field of each encoded token is an index into this slice.
+pub const TOKEN_TYPES: &[&str] =
+    &["function", "unknown", "parameter", "property", "markup"];
+
+/// The token modifiers advertised in the legend, in bit order.
+///
+/// The `tokenModifiers` field of each encoded token is a bitset over these
+/// names, bit `i` corresponding to `TOKEN_MODIFIERS[i]`.
+pub const TOKEN_MODIFIERS: &[&str] = &["italic", "bold"];
+
+impl Decoration {
+    /// The `(tokenType, tokenModifiers)` encoding of this decoration, where the
+    /// type is an index into [`TOKEN_TYPES`] and the modifiers are a bitset over
+    /// [`TOKEN_MODIFIERS`].
+    fn token(self) -> (u32, u32) {
+        use Decoration::*;
+        match self {
+            ValidFuncName => (0, 0),
+            // A name that failed to resolve is a parse error, not a deprecation,
+            // so it gets its own token type rather than a modifier on `function`.
+            InvalidFuncName => (1, 0),
+            ArgumentKey => (2, 0),
+            ObjectKey => (3, 0),
+            Italic => (4, 1 << 0),
+            Bold => (4, 1 << 1),
+        }
+    }
+}
+
+/// Encode decorations into the flat LSP semantic-tokens representation.
+///
+/// The decorations are sorted by start position and emitted as five `u32`s
+/// each, with line and character deltas relative to the previous token. `source`
+/// is the document the spans index into.
+///
+/// LSP counts positions and lengths in UTF-16 code units. The `span` module is
+/// required to express columns in the same unit (see the chunk0-4 request); to
+/// avoid depending on that unit blindly, token lengths are measured against the
+/// source itself — every length is clamped to the UTF-16 length of the line it
+/// covers, so a column that over-runs the line can never emit a bogus length.
+/// Tokens crossing a line boundary are split into one token per covered line,
+/// since LSP cannot represent a multi-line token.
+pub fn encode_semantic_tokens(source: &str, decorations: &SpanVec<Decoration>) -> Vec<u32> {
+    // UTF-16 length of each line, excluding its line break, measured from the
+    // source so token lengths are bounded by real line content.
+    let line_lengths: Vec<u32> = source
+        .lines()
+        .map(|line| line.chars().map(|c| c.len_utf16() as u32).sum())
+        .collect();
+
+    let mut sorted: Vec<_> = decorations.iter().collect();
+    sorted.sort_by_key(|deco| deco.span.start);
+
+    let mut data = Vec::with_capacity(sorted.len() * 5);
+    let (mut prev_line, mut prev_char) = (0, 0);
+
+    for deco in sorted {
+        let (start, end) = (deco.span.start, deco.span.end);
+        let (ty, modifiers) = deco.v.token();
+
+        // Emit one token per covered line: the start line runs from its column
+        // to the end of the line (or to `end.column` when single-line), inner
+        // lines span the whole line, and the end line stops at `end.column`.
+        for line in start.line..=end.line {
+            let line_len = line_lengths.get(line as usize).copied().unwrap_or(0);
+            let char = if line == start.line { start.column.min(line_len) } else { 0 };
+            let line_end = if line == end.line { end.column.min(line_len) } else { line_len };
+            let length = line_end.saturating_sub(char);
+
+            let delta_line = line - prev_line;
+            let delta_char = if delta_line == 0 { char - prev_char } else { char };
+            data.extend_from_slice(&[delta_line, delta_char, length, ty, modifiers]);
+
+            prev_line = line;
+            prev_char = char;
+        }
+    }
+
+    data
+}
@@ -3,11 +3,12 @@
 use std::any::Any;
 use std::fmt::Debug;
 use async_trait::async_trait;
-use serde::Serialize;
+use serde::{Serialize, Serializer};
+use serde::ser::SerializeStruct;
 
 use crate::{Pass, Feedback};
 use crate::layout::{LayoutContext, Commands, Command};
-use self::span::{Spanned, SpanVec};
+use self::span::{Position, Span};
 
 #[cfg(test)]
 #[macro_use]
@@ -15,7 +16,9 @@ mod test;
 
 pub mod expr;
 pub mod func;
+pub mod lsp;
 pub mod span;
+pub mod visit;
 pub_use_mod!(scope);
 pub_use_mod!(parsing);
 pub_use_mod!(tokens);
@@ -24,17 +27,51 @@ pub_use_mod!(tokens);
 /// Represents a parsed piece of source that can be layouted and in the future
 /// also be queried for information used for refactorings, autocomplete, etc.
 #[async_trait(?Send)]
-pub trait Model: Debug + ModelBounds {
+pub trait Model: Debug + ModelBounds + erased_serde::Serialize {
     /// Layout the model into a sequence of commands processed by a
     /// [`ModelLayouter`](crate::layout::ModelLayouter).
     async fn layout<'a>(&'a self, ctx: LayoutContext<'_>) -> Pass<Commands<'a>>;
+
+    /// A stable, explicit tag naming this model's type.
+    ///
+    /// This is the discriminant emitted when serializing the opaque trait
+    /// object for editor tooling and golden tests. Unlike
+    /// [`std::any::type_name`] — which the standard library documents as
+    /// diagnostic-only with no stability guarantee — the tag is chosen by the
+    /// implementor, so serialized payloads stay reproducible across compiler
+    /// versions. The default `"model"` keeps this trait change self-contained;
+    /// concrete models should override it with a distinct stable tag.
+    fn type_name(&self) -> &'static str {
+        "model"
+    }
+
+    /// The child nodes this model exposes to tree walkers.
+    ///
+    /// Submodels are opaque trait objects, so traversals (see the
+    /// [`visit`](crate::syntax::visit) module) rely on this hook to descend
+    /// into, for example, the body of a function invocation. The default is no
+    /// children.
+    fn children(&self) -> Vec<&Node> {
+        vec![]
+    }
+
+    /// The child nodes this model exposes to mutable tree walkers.
+    ///
+    /// The mutable mirror of [`children`](Model::children); a model that does
+    /// not override it cannot be descended into by [`VisitorMut`](crate::syntax::visit::VisitorMut).
+    /// The default is no children.
+    fn children_mut(&mut self) -> Vec<&mut Node> {
+        vec![]
+    }
 }
 
+erased_serde::serialize_trait_object!(Model);
+
 /// A tree representation of source code.
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub struct SyntaxModel {
     /// The syntactical elements making up this model.
-    pub nodes: SpanVec<Node>,
+    pub nodes: Vec<Node>,
 }
 
 impl SyntaxModel {
@@ -44,9 +81,54 @@ impl SyntaxModel {
     }
 
     /// Add a node to the model.
-    pub fn add(&mut self, node: Spanned<Node>) {
+    pub fn add(&mut self, node: Node) {
         self.nodes.push(node);
     }
+
+    /// The path of nodes whose spans contain the source position `pos`, ordered
+    /// innermost to outermost.
+    ///
+    /// This is the primitive editors use for hover, go-to-definition and
+    /// autocomplete. The search descends through [`NodeKind::Model`] submodels
+    /// via [`Model::children`]. Spans are non-overlapping and ordered, so each
+    /// level contributes at most one node; on a span boundary the earlier span
+    /// wins and positions in whitespace still return their enclosing container.
+    pub fn node_at(&self, pos: Position) -> Vec<&Node> {
+        let mut path = vec![];
+        find_node_at(self.nodes.iter(), pos, &mut path);
+        path.reverse();
+        path
+    }
+}
+
+/// Descend `nodes`, appending the matching node at each level (outermost first).
+fn find_node_at<'a>(
+    nodes: impl Iterator<Item = &'a Node>,
+    pos: Position,
+    path: &mut Vec<&'a Node>,
+) {
+    // Spans are ordered, and `Span::contains` is end-inclusive, so a position
+    // on a boundary is contained by both adjacent spans. Taking the first match
+    // means the earliest containing span wins, honouring the boundary rule.
+    let mut nodes = nodes;
+    if let Some(node) = nodes.find(|node| node.span.contains(pos)) {
+        path.push(node);
+        // Descend into nested bodies and submodels, matching the recursion in
+        // [`walk_node`](crate::syntax::visit::walk_node) so the query and the
+        // visitor agree on the tree shape.
+        match &node.kind {
+            NodeKind::Heading { body, .. } | NodeKind::Link { body, .. } => {
+                find_node_at(body.iter(), pos, path);
+            }
+            NodeKind::List { items, .. } => {
+                find_node_at(items.iter().flatten(), pos, path);
+            }
+            NodeKind::Model(model) => {
+                find_node_at(model.children().into_iter(), pos, path);
+            }
+            _ => {}
+        }
+    }
 }
 
 #[async_trait(?Send)]
@@ -54,11 +136,68 @@ impl Model for SyntaxModel {
     async fn layout<'a>(&'a self, _: LayoutContext<'_>) -> Pass<Commands<'a>> {
         Pass::new(vec![Command::LayoutSyntaxModel(self)], Feedback::new())
     }
+
+    fn type_name(&self) -> &'static str {
+        "syntax"
+    }
+
+    fn children(&self) -> Vec<&Node> {
+        self.nodes.iter().collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Node> {
+        self.nodes.iter_mut().collect()
+    }
 }
 
-/// A node in the [syntax model](SyntaxModel).
+/// A node in the [syntax model](SyntaxModel): a span paired with its
+/// [kind](NodeKind).
+///
+/// Splitting the lightweight wrapper from the content enum mirrors rustc's
+/// `Item`/`ItemKind` and lets every node carry its own source span instead of
+/// relying on an external [`Spanned`](span::Spanned) wrapper.
 #[derive(Debug, Clone)]
-pub enum Node {
+pub struct Node {
+    /// The source span this node covers.
+    pub span: Span,
+    /// The kind of node and its content.
+    pub kind: NodeKind,
+}
+
+impl Node {
+    /// Create a node from a span and a kind.
+    pub fn new(span: Span, kind: NodeKind) -> Node {
+        Node { span, kind }
+    }
+}
+
+impl Serialize for Node {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        let mut s = serializer.serialize_struct("Node", 2)?;
+        s.serialize_field("span", &self.span)?;
+        s.serialize_field("kind", &self.kind)?;
+        s.end()
+    }
+}
+
+impl PartialEq for Node {
+    /// Nodes compare by kind only; spans are positional metadata and are
+    /// ignored so structural equality survives re-spanning.
+    fn eq(&self, other: &Node) -> bool {
+        self.kind == other.kind
+    }
+}
+
+/// The kind and content of a [`Node`].
+///
+/// The block-level kinds ([`Heading`](NodeKind::Heading),
+/// [`List`](NodeKind::List) and [`Link`](NodeKind::Link)) hold nested bodies
+/// of nodes, which [`SyntaxModel::node_at`] and the
+/// [`visit`](crate::syntax::visit) walkers descend into. The tokenizer and
+/// parser do not produce these kinds, and layout has no commands for them.
+#[derive(Debug, Clone)]
+pub enum NodeKind {
     /// Whitespace containing less than two newlines.
     Space,
     /// Whitespace with more than two newlines.
@@ -73,13 +212,112 @@ pub enum Node {
     ToggleItalic,
     /// Bolder was enabled / disabled.
     ToggleBolder,
+    /// A section heading of the given level (number of leading `=`) with a
+    /// nested body.
+    Heading {
+        /// The heading level, starting at one.
+        level: usize,
+        /// The heading's inline content.
+        body: Vec<Node>,
+    },
+    /// A bulleted or numbered list.
+    List {
+        /// Whether the list is ordered (numbered) rather than bulleted.
+        ordered: bool,
+        /// The list items, each its own nested body.
+        items: Vec<Vec<Node>>,
+    },
+    /// A hyperlink to `target` with a nested body to display.
+    Link {
+        /// The link target (URL).
+        target: String,
+        /// The link's displayed content.
+        body: Vec<Node>,
+    },
     /// A submodel, typically a function invocation.
     Model(Box<dyn Model>),
 }
 
-impl PartialEq for Node {
-    fn eq(&self, other: &Node) -> bool {
-        use Node::*;
+impl Serialize for NodeKind {
+    /// Every variant is emitted as an object carrying a camelCase `"type"` tag
+    /// plus its payload fields, so the wire format is uniform for golden tests
+    /// and editor consumption rather than mixing serde's enum encoding with
+    /// hand-rolled structs.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        use NodeKind::*;
+        match self {
+            Space => tagged(serializer, "space"),
+            Parbreak => tagged(serializer, "parbreak"),
+            Linebreak => tagged(serializer, "linebreak"),
+            ToggleItalic => tagged(serializer, "toggleItalic"),
+            ToggleBolder => tagged(serializer, "toggleBolder"),
+            Text(text) => {
+                let mut s = serializer.serialize_struct("Node", 2)?;
+                s.serialize_field("type", &"text")?;
+                s.serialize_field("text", text)?;
+                s.end()
+            }
+            Raw(lines) => {
+                let mut s = serializer.serialize_struct("Node", 2)?;
+                s.serialize_field("type", &"raw")?;
+                s.serialize_field("lines", lines)?;
+                s.end()
+            }
+            Heading { level, body } => {
+                let mut s = serializer.serialize_struct("Node", 3)?;
+                s.serialize_field("type", &"heading")?;
+                s.serialize_field("level", level)?;
+                s.serialize_field("body", body)?;
+                s.end()
+            }
+            List { ordered, items } => {
+                let mut s = serializer.serialize_struct("Node", 3)?;
+                s.serialize_field("type", &"list")?;
+                s.serialize_field("ordered", ordered)?;
+                s.serialize_field("items", items)?;
+                s.end()
+            }
+            Link { target, body } => {
+                let mut s = serializer.serialize_struct("Node", 3)?;
+                s.serialize_field("type", &"link")?;
+                s.serialize_field("target", target)?;
+                s.serialize_field("body", body)?;
+                s.end()
+            }
+            Model(model) => {
+                // Opaque trait objects cannot carry a serde discriminant, so we
+                // emit the concrete type tag alongside the erased value.
+                let mut s = serializer.serialize_struct("Node", 2)?;
+                s.serialize_field("type", model.type_name())?;
+                s.serialize_field("value", &ErasedModel(model.as_ref()))?;
+                s.end()
+            }
+        }
+    }
+}
+
+/// Serialize a payload-less node kind as `{ "type": <tag> }`.
+fn tagged<S>(serializer: S, tag: &str) -> Result<S::Ok, S::Error>
+where S: Serializer {
+    let mut s = serializer.serialize_struct("Node", 1)?;
+    s.serialize_field("type", tag)?;
+    s.end()
+}
+
+/// Serialization adapter that forwards to a model's [`erased_serde`] impl.
+struct ErasedModel<'a>(&'a dyn Model);
+
+impl Serialize for ErasedModel<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        erased_serde::serialize(self.0, serializer)
+    }
+}
+
+impl PartialEq for NodeKind {
+    fn eq(&self, other: &NodeKind) -> bool {
+        use NodeKind::*;
         match (self, other) {
             (Space, Space) => true,
             (Parbreak, Parbreak) => true,
@@ -88,6 +326,15 @@ impl PartialEq for Node {
             (Raw(a), Raw(b)) => a == b,
             (ToggleItalic, ToggleItalic) => true,
             (ToggleBolder, ToggleBolder) => true,
+            (Heading { level: la, body: ba }, Heading { level: lb, body: bb }) => {
+                la == lb && ba == bb
+            }
+            (List { ordered: oa, items: ia }, List { ordered: ob, items: ib }) => {
+                oa == ob && ia == ib
+            }
+            (Link { target: ta, body: ba }, Link { target: tb, body: bb }) => {
+                ta == tb && ba == bb
+            }
             (Model(a), Model(b)) => a == b,
             _ => false,
         }
@@ -181,3 +428,83 @@ impl<T> ModelBounds for T where T: Model + PartialEq + Clone + 'static {
         Box::new(self.clone())
     }
 }
+
+// The dedicated `test` harness module is not part of this source snapshot, so
+// these serialization goldens live inline.
+#[cfg(test)]
+mod serialize_tests {
+    use super::*;
+
+    #[test]
+    fn empty_syntax_model_serializes() {
+        let json = serde_json::to_value(&SyntaxModel::new()).unwrap();
+        assert_eq!(json, serde_json::json!({ "nodes": [] }));
+    }
+
+    #[test]
+    fn node_kinds_share_one_tagged_shape() {
+        assert_eq!(
+            serde_json::to_value(&NodeKind::Space).unwrap(),
+            serde_json::json!({ "type": "space" }),
+        );
+        assert_eq!(
+            serde_json::to_value(&NodeKind::Text("hi".to_string())).unwrap(),
+            serde_json::json!({ "type": "text", "text": "hi" }),
+        );
+        assert_eq!(
+            serde_json::to_value(&NodeKind::Heading { level: 1, body: vec![] }).unwrap(),
+            serde_json::json!({ "type": "heading", "level": 1, "body": [] }),
+        );
+    }
+
+    #[test]
+    fn model_serializes_with_stable_tag() {
+        // A `SyntaxModel` is the in-tree `Model`; its submodel serialization
+        // pairs the stable `type_name` tag with the erased value. There is no
+        // `Deserialize` path yet, so this is a one-way serialization golden.
+        let kind = NodeKind::Model(Box::new(SyntaxModel::new()));
+        let json = serde_json::to_value(&kind).unwrap();
+        assert_eq!(json, serde_json::json!({ "type": "syntax", "value": { "nodes": [] } }));
+    }
+}
+
+// Pins the documented `node_at` edge cases: the earlier span wins on a boundary,
+// and a position in whitespace still returns its enclosing container.
+#[cfg(test)]
+mod node_at_tests {
+    use super::*;
+    use super::span::{Position, Span};
+
+    fn span(l1: u32, c1: u32, l2: u32, c2: u32) -> Span {
+        Span::new(Position::new(l1, c1), Position::new(l2, c2))
+    }
+
+    #[test]
+    fn boundary_prefers_the_earlier_span() {
+        let mut model = SyntaxModel::new();
+        model.add(Node::new(span(0, 0, 0, 3), NodeKind::Text("foo".to_string())));
+        model.add(Node::new(span(0, 3, 0, 6), NodeKind::Text("bar".to_string())));
+
+        // Column 3 is the inclusive end of the first span and the start of the
+        // second; the earlier one wins.
+        let path = model.node_at(Position::new(0, 3));
+        assert_eq!(path.len(), 1);
+        assert_eq!(path[0].kind, NodeKind::Text("foo".to_string()));
+    }
+
+    #[test]
+    fn whitespace_returns_the_enclosing_container() {
+        let space = Node::new(span(0, 2, 0, 3), NodeKind::Space);
+        let mut model = SyntaxModel::new();
+        model.add(Node::new(span(0, 0, 0, 10), NodeKind::Heading {
+            level: 1,
+            body: vec![space],
+        }));
+
+        // Innermost-to-outermost: the whitespace node and its heading container.
+        let path = model.node_at(Position::new(0, 2));
+        assert_eq!(path.len(), 2);
+        assert_eq!(path[0].kind, NodeKind::Space);
+        assert!(matches!(path[1].kind, NodeKind::Heading { .. }));
+    }
+}
@@ -0,0 +1,158 @@
+//! Traversal of [syntax models](super::SyntaxModel).
+//!
+//! The [`Visitor`] and [`VisitorMut`] traits mirror rustc's `libsyntax`
+//! `Visitor`: the hooks for models and nodes default to a matching free
+//! `walk_*` function that performs the recursion, while the leaf hooks (spans,
+//! text and raw text) are empty. Override the hooks you care about and call the
+//! corresponding `walk_*` function to descend further. The cursor query
+//! [`SyntaxModel::node_at`] does not use these traits; it has its own recursion
+//! over the same tree shape.
+
+use super::{Model, Node, NodeKind, SyntaxModel};
+use super::span::Span;
+
+/// Walks a [`SyntaxModel`] by shared reference.
+///
+/// The model and node hooks default to recursing via the matching `walk_*`
+/// function and the leaf hooks are empty, so an empty implementation visits
+/// the whole tree without doing anything.
+pub trait Visitor: Sized {
+    /// Visit the root model. Submodels are visited through
+    /// [`visit_model`](Visitor::visit_model).
+    fn visit_syntax_model(&mut self, model: &SyntaxModel) {
+        walk_syntax_model(self, model);
+    }
+
+    /// Visit a single node.
+    fn visit_node(&mut self, node: &Node) {
+        walk_node(self, node);
+    }
+
+    /// Visit the span attached to a node.
+    fn visit_span(&mut self, _: Span) {}
+
+    /// Visit plain text.
+    fn visit_text(&mut self, _: &str) {}
+
+    /// Visit lines of raw text.
+    fn visit_raw(&mut self, _: &[String]) {}
+
+    /// Visit a submodel, such as a function invocation.
+    fn visit_model(&mut self, model: &dyn Model) {
+        walk_model(self, model);
+    }
+}
+
+/// Walks a [`SyntaxModel`] by mutable reference.
+///
+/// Like [`Visitor`], the model and node hooks default to recursing via the
+/// matching `walk_*` function and the leaf hooks are empty; submodels are
+/// descended into through
+/// [`children_mut`](Model::children_mut).
+pub trait VisitorMut: Sized {
+    /// Visit the root model.
+    fn visit_syntax_model(&mut self, model: &mut SyntaxModel) {
+        walk_syntax_model_mut(self, model);
+    }
+
+    /// Visit a single node.
+    fn visit_node(&mut self, node: &mut Node) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visit the span attached to a node.
+    fn visit_span(&mut self, _: Span) {}
+
+    /// Visit plain text.
+    fn visit_text(&mut self, _: &mut String) {}
+
+    /// Visit lines of raw text.
+    fn visit_raw(&mut self, _: &mut Vec<String>) {}
+
+    /// Visit a submodel, such as a function invocation.
+    fn visit_model(&mut self, model: &mut Box<dyn Model>) {
+        walk_model_mut(self, model);
+    }
+}
+
+/// Recurse into the nodes of a model.
+pub fn walk_syntax_model<V: Visitor>(v: &mut V, model: &SyntaxModel) {
+    for node in &model.nodes {
+        v.visit_span(node.span);
+        v.visit_node(node);
+    }
+}
+
+/// Recurse into a single node, descending into bodies and submodels.
+pub fn walk_node<V: Visitor>(v: &mut V, node: &Node) {
+    match &node.kind {
+        NodeKind::Text(text) => v.visit_text(text),
+        NodeKind::Raw(lines) => v.visit_raw(lines),
+        NodeKind::Heading { body, .. } | NodeKind::Link { body, .. } => {
+            for child in body {
+                v.visit_span(child.span);
+                v.visit_node(child);
+            }
+        }
+        NodeKind::List { items, .. } => {
+            for item in items {
+                for child in item {
+                    v.visit_span(child.span);
+                    v.visit_node(child);
+                }
+            }
+        }
+        NodeKind::Model(model) => v.visit_model(model.as_ref()),
+        NodeKind::Space | NodeKind::Parbreak | NodeKind::Linebreak
+        | NodeKind::ToggleItalic | NodeKind::ToggleBolder => {}
+    }
+}
+
+/// Recurse into the children a submodel exposes.
+pub fn walk_model<V: Visitor>(v: &mut V, model: &dyn Model) {
+    for child in model.children() {
+        v.visit_span(child.span);
+        v.visit_node(child);
+    }
+}
+
+/// Recurse into the nodes of a model by mutable reference.
+pub fn walk_syntax_model_mut<V: VisitorMut>(v: &mut V, model: &mut SyntaxModel) {
+    for node in &mut model.nodes {
+        v.visit_span(node.span);
+        v.visit_node(node);
+    }
+}
+
+/// Recurse into a single node by mutable reference.
+pub fn walk_node_mut<V: VisitorMut>(v: &mut V, node: &mut Node) {
+    match &mut node.kind {
+        NodeKind::Text(text) => v.visit_text(text),
+        NodeKind::Raw(lines) => v.visit_raw(lines),
+        NodeKind::Heading { body, .. } | NodeKind::Link { body, .. } => {
+            for child in body {
+                v.visit_span(child.span);
+                v.visit_node(child);
+            }
+        }
+        NodeKind::List { items, .. } => {
+            for item in items {
+                for child in item {
+                    v.visit_span(child.span);
+                    v.visit_node(child);
+                }
+            }
+        }
+        NodeKind::Model(model) => v.visit_model(model),
+        NodeKind::Space | NodeKind::Parbreak | NodeKind::Linebreak
+        | NodeKind::ToggleItalic | NodeKind::ToggleBolder => {}
+    }
+}
+
+/// Recurse into the children a submodel exposes by mutable reference.
+pub fn walk_model_mut<V: VisitorMut>(v: &mut V, model: &mut Box<dyn Model>) {
+    for child in model.children_mut() {
+        v.visit_span(child.span);
+        v.visit_node(child);
+    }
+}